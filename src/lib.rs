@@ -1,26 +1,87 @@
 use std::{fmt::Display, str::FromStr, error::Error};
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct IpAddressBlock {
-    pub address: [u8; 4],
-    pub mask: u8,
+// An address block is either an IPv4 or an IPv6 network, carrying the address
+// itself alongside the prefix length (the "mask"). Keeping both widths in one
+// enum lets the rest of the crate (subnetting, parsing, display) stay generic
+// over the address family instead of duplicating every algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpAddressBlock {
+    V4 { address: [u8; 4], mask: u8 },
+    V6 { address: [u16; 8], mask: u8 },
 }
 
-// This is wrapper (newtype) around u32, so it can be copied bit by bit
+// Wrapper (newtype) around the raw 32-bit IPv4 mask, so it can be displayed in
+// dotted-decimal form (e.g. "255.255.255.224") instead of as a prefix length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubnetMask(u32);
+
+impl SubnetMask {
+    // Panics if the prefix length is not in the range {0, 1, ..., 32}
+    pub fn from_prefix_len(mask: u8) -> Self {
+        if mask > 32 {
+            panic!("{mask} is not a valid mask");
+        }
+        let bits = if mask == 0 { 0 } else { !0_u32 << (32 - mask) };
+        Self(bits)
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
+    // The wildcard mask is the bitwise complement of the subnet mask, e.g.
+    // 255.255.255.224 -> 0.0.0.31
+    pub fn wildcard(&self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl Display for SubnetMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let octets = self.0.to_be_bytes();
+        write!(f, "{}.{}.{}.{}", octets[0], octets[1], octets[2], octets[3])
+    }
+}
+
+// This is wrapper (newtype) around u128, so it can be copied bit by bit.
+// u128 rather than u32 because an IPv6 VLSM requirement can ask for far more
+// than 2^32 hosts
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
-pub struct NetworkHosts(u32);
+pub struct NetworkHosts(u128);
 
 impl NetworkHosts {
-    pub fn new(hosts: u32) -> Self {
+    pub fn new(hosts: u128) -> Self {
         Self(hosts)
     }
 
-    pub fn hosts(&self) -> u32 {
+    pub fn hosts(&self) -> u128 {
         self.0
     }
 
-    pub fn required_mask(&self) -> u8 {
-        32 - minimum_bits_needed(self.0 as usize + 2)
+    // The prefix length needed to hold this many hosts within an address
+    // family 'address_width' bits wide (32 for v4, 128 for v6). Returns None
+    // if even a /0 of that family doesn't have enough host bits
+    pub fn required_mask(&self, address_width: u8) -> Option<u8> {
+        address_width.checked_sub(self.required_host_bits())
+    }
+
+    // Number of host bits needed to hold this many hosts, independent of the
+    // address family; the caller subtracts this from the family's address
+    // width (32 for v4, 128 for v6) to get the prefix length to use.
+    // Saturates instead of overflowing for host counts within 2 of u128::MAX
+    // (there is no address family wide enough to hold them anyway, so the
+    // huge-but-technically-wrong bit count this saturates to still ends up
+    // rejected by 'required_mask'/the caller's own capacity check)
+    fn required_host_bits(&self) -> u8 {
+        minimum_bits_needed(self.0.saturating_add(2))
+    }
+}
+
+impl FromStr for NetworkHosts {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u128>().map(Self::new)
     }
 }
 
@@ -63,10 +124,40 @@ impl Display for IpAddressParseError {
     }
 }
 
+// A run of 'bits' low bits set to 1, e.g. low_bits_mask(3) == 0b111. Unlike a
+// plain '(1_u128 << bits) - 1', this is well-defined for 'bits == 128' (a
+// v6 /0 has exactly this many host bits), where a raw shift by the full
+// width would panic regardless of the value being shifted
+fn low_bits_mask(bits: u8) -> u128 {
+    if bits >= 128 {
+        u128::MAX
+    } else {
+        (1_u128 << bits) - 1
+    }
+}
+
+// Shifts, but saturate to 0 instead of panicking when 'bits' is the full
+// width of a u128 (again, the v6 /0 case)
+fn shl_safe(value: u128, bits: u8) -> u128 {
+    if bits >= 128 {
+        0
+    } else {
+        value << bits
+    }
+}
+
+fn shr_safe(value: u128, bits: u8) -> u128 {
+    if bits >= 128 {
+        0
+    } else {
+        value >> bits
+    }
+}
+
 // Minimum bits needed to represent the quantity num
 // Returns u8 because it is the minimum primitive type
 // which can hold the maximum number of usize
-fn minimum_bits_needed(mut num: usize) -> u8 {
+fn minimum_bits_needed(mut num: u128) -> u8 {
     if num == 0 {
         panic!("num is equal to 0");
     }
@@ -79,13 +170,22 @@ fn minimum_bits_needed(mut num: usize) -> u8 {
     }
     counter
 }
+
 impl IpAddressBlock {
-    // Panics if the network mask is not in the range {0, 1, 2, ..., 32}
+    // Panics if the network mask is not in the range {0, 1, ..., 32}
     pub fn new(address: [u8; 4], mask: u8) -> Self {
         if mask > 32 {
             panic!("{mask} is not a valid mask");
         }
-        Self { address, mask }
+        Self::V4 { address, mask }
+    }
+
+    // Panics if the network mask is not in the range {0, 1, ..., 128}
+    pub fn new_v6(address: [u16; 8], mask: u8) -> Self {
+        if mask > 128 {
+            panic!("{mask} is not a valid mask");
+        }
+        Self::V6 { address, mask }
     }
 
     pub fn from_u32_address(u32_addr: u32, mask: u8) -> Self {
@@ -93,34 +193,138 @@ impl IpAddressBlock {
             ((u32_addr >> 24) & 0xFF) as u8,
             ((u32_addr >> 16) & 0xFF) as u8,
             ((u32_addr >> 8) & 0xFF) as u8,
-            ((u32_addr >> 0) & 0xFF) as u8,
+            (u32_addr & 0xFF) as u8,
         ];
 
         Self::new(address, mask)
     }
 
+    pub fn from_u128_address(u128_addr: u128, mask: u8) -> Self {
+        let mut address = [0_u16; 8];
+        for (idx, hextet) in address.iter_mut().enumerate() {
+            let shift = (7 - idx) * 16;
+            *hextet = ((u128_addr >> shift) & 0xFFFF) as u16;
+        }
+
+        Self::new_v6(address, mask)
+    }
+
+    pub fn is_v6(&self) -> bool {
+        matches!(self, Self::V6 { .. })
+    }
+
+    pub fn mask(&self) -> u8 {
+        match self {
+            Self::V4 { mask, .. } => *mask,
+            Self::V6 { mask, .. } => *mask,
+        }
+    }
+
+    // Number of bits in this address family: 32 for v4, 128 for v6
+    pub fn address_width(&self) -> u8 {
+        match self {
+            Self::V4 { .. } => 32,
+            Self::V6 { .. } => 128,
+        }
+    }
+
     pub fn subnet_flsm(&self, num_networks: usize) -> Option<Vec<Self>> {
         // TODO: consider the idea of returning an iterator instead of Vec
         let new_mask = self.new_mask_for(num_networks)?;
 
-        let remaining_bits = 32 - new_mask;
-        let as_u32 = self.address_as_u32();
-        let bitmask = !((1 << remaining_bits) - 1);
+        let remaining_bits = self.address_width() - new_mask;
+        let as_int = self.as_int();
+        let bitmask = !low_bits_mask(remaining_bits);
 
-        let mut network_id = (as_u32 & bitmask) >> remaining_bits;
-        let mut blocks = Vec::with_capacity(num_networks);
+        let first_network_id = shr_safe(as_int & bitmask, remaining_bits);
+        let blocks = (first_network_id..first_network_id + num_networks as u128)
+            .map(|network_id| self.with_value(shl_safe(network_id, remaining_bits), new_mask))
+            .collect();
 
-        for _ in 0..num_networks {
-            let new_as_u32 = network_id << remaining_bits;
-            blocks.push(Self::from_u32_address(new_as_u32, new_mask));
-            network_id += 1;
+        Some(blocks)
+    }
+
+    pub fn available_hosts(&self) -> u128 {
+        self.host_count()
+    }
+
+    // The dotted-decimal subnet mask for this block's prefix length. Only
+    // meaningful for IPv4 blocks; IPv6 prefixes have no conventional
+    // dotted-decimal mask, so this returns None for those
+    pub fn subnet_mask(&self) -> Option<SubnetMask> {
+        match self {
+            Self::V4 { mask, .. } => Some(SubnetMask::from_prefix_len(*mask)),
+            Self::V6 { .. } => None,
         }
+    }
 
-        Some(blocks)
+    // The network address: the address with every host bit cleared
+    pub fn network_address(&self) -> Self {
+        self.with_value(self.as_int() & self.mask_as_int(), self.mask())
+    }
+
+    // The broadcast address: the network address with every host bit set.
+    // For /32 (and the IPv6 equivalent /128) this is just the network address
+    // itself; for /31 (and /127) it is the upper of the block's two addresses
+    pub fn broadcast_address(&self) -> Self {
+        let network = self.network_address();
+        self.with_value(network.as_int() | self.wildcard_as_int(), self.mask())
+    }
+
+    // The first usable host address, or None for /31, /32 (and /127, /128)
+    // blocks, which have no usable-host range
+    pub fn first_host(&self) -> Option<Self> {
+        if self.address_width() - self.mask() <= 1 {
+            return None;
+        }
+        let network = self.network_address();
+        Some(self.with_value(network.as_int() + 1, self.mask()))
     }
 
-    pub fn available_hosts(&self) -> u32 {
-        (1 << (32 - self.mask)) - 2
+    // The last usable host address, or None for /31, /32 (and /127, /128)
+    // blocks, which have no usable-host range
+    pub fn last_host(&self) -> Option<Self> {
+        if self.address_width() - self.mask() <= 1 {
+            return None;
+        }
+        let broadcast = self.broadcast_address();
+        Some(self.with_value(broadcast.as_int() - 1, self.mask()))
+    }
+
+    // Number of usable host addresses. /31 and /32 (and /127, /128) blocks
+    // have no network/broadcast split, so they report zero usable hosts
+    pub fn host_count(&self) -> u128 {
+        let host_bits = self.address_width() - self.mask();
+        if host_bits <= 1 {
+            0
+        } else if host_bits >= 128 {
+            // '1 << 128' overflows a u128, but 2^128 - 2 itself is exactly
+            // u128::MAX - 1, which is what a v6 /0 has
+            u128::MAX - 1
+        } else {
+            (1_u128 << host_bits) - 2
+        }
+    }
+
+    // The full bitmask (all ones) for this block's address width
+    fn full_mask_int(&self) -> u128 {
+        low_bits_mask(self.address_width())
+    }
+
+    // The prefix mask (e.g. /27 -> a run of 27 ones followed by 5 zeroes),
+    // widened to u128 so it applies to both address families
+    fn mask_as_int(&self) -> u128 {
+        let host_bits = self.address_width() - self.mask();
+        if host_bits == 0 {
+            self.full_mask_int()
+        } else {
+            self.full_mask_int() & !low_bits_mask(host_bits)
+        }
+    }
+
+    // The wildcard mask: the bitwise complement of the prefix mask
+    fn wildcard_as_int(&self) -> u128 {
+        self.full_mask_int() & !self.mask_as_int()
     }
 
     // Assign each network host a subnetwork using VLSM. If it not possible, return
@@ -130,7 +334,7 @@ impl IpAddressBlock {
     // IMO, the user only constructs a 'Vec<NetworkHosts>' to use this method
     pub fn subnet_vlsm(&self, mut subnets: Vec<NetworkHosts>) -> Option<Vec<(NetworkHosts, Self)>> {
         // Check if this address block can hold all the given network hosts
-        let total_hosts: u32 = subnets.iter().map(NetworkHosts::hosts).sum();
+        let total_hosts: u128 = subnets.iter().map(NetworkHosts::hosts).sum();
 
         if total_hosts > self.available_hosts() {
             return None;
@@ -138,30 +342,227 @@ impl IpAddressBlock {
 
         subnets.sort_unstable_by(|x, y| y.cmp(x));
 
-        let mut new_addr_as_u32 = self.address_as_u32();
+        let mut new_addr_as_int = self.as_int();
         let mut result = Vec::with_capacity(subnets.len());
 
         for subnet in subnets.into_iter() {
-            result.push((
-                subnet,
-                Self::from_u32_address(new_addr_as_u32, subnet.required_mask()),
-            ));
+            let required_mask = subnet.required_mask(self.address_width())?;
+
+            result.push((subnet, self.with_value(new_addr_as_int, required_mask)));
 
-            let remaining_bits = 32 - subnet.required_mask();
-            let bitmask = !((1 << remaining_bits) - 1);
-            let new_network_id = ((new_addr_as_u32 & bitmask) >> remaining_bits) + 1;
-            new_addr_as_u32 = new_network_id << remaining_bits;
+            let remaining_bits = self.address_width() - required_mask;
+            let bitmask = !low_bits_mask(remaining_bits);
+            let new_network_id = shr_safe(new_addr_as_int & bitmask, remaining_bits) + 1;
+            new_addr_as_int = shl_safe(new_network_id, remaining_bits);
         }
 
         Some(result)
     }
 
-    // Converts the array representing the address to a u32
+    // Decomposes an arbitrary inclusive IPv4 address range into the minimal
+    // set of CIDR blocks that cover it exactly. Returns an empty vec if
+    // 'start' is greater than 'end'
+    pub fn range_to_prefixes(start: u32, end: u32) -> Vec<Self> {
+        if start > end {
+            return Vec::new();
+        }
+
+        let mut blocks = Vec::new();
+        let mut addr = start as u64;
+        let end = end as u64;
+
+        while addr <= end {
+            // The block must be aligned at 'addr', i.e. its size can be at
+            // most 2^(trailing zeroes of addr)
+            let max_align_bits = if addr == 0 { 32 } else { addr.trailing_zeros() };
+
+            // The block must also not overshoot 'end'
+            let max_size = end - addr + 1;
+            let max_fit_bits = 63 - max_size.leading_zeros();
+
+            let block_bits = max_align_bits.min(max_fit_bits).min(32);
+            let mask = 32 - block_bits as u8;
+
+            blocks.push(Self::from_u32_address(addr as u32, mask));
+            addr += 1_u64 << block_bits;
+        }
+
+        blocks
+    }
+
+    // Merges a list of blocks into the fewest covering, non-overlapping
+    // prefixes: blocks fully contained in another are dropped, and sibling
+    // blocks (the two halves of a less specific prefix) are repeatedly
+    // combined until no further merge is possible
+    pub fn aggregate(blocks: Vec<Self>) -> Vec<Self> {
+        if blocks.is_empty() {
+            return blocks;
+        }
+
+        // Normalize every block to its network address up front, so a block
+        // entered with non-network-aligned host bits (e.g. "10.0.0.5/26")
+        // still compares and merges correctly against its true sibling
+        let mut blocks: Vec<Self> = blocks.into_iter().map(|b| b.network_address()).collect();
+        blocks.sort_unstable_by_key(|b| (b.as_int(), b.mask()));
+
+        let mut deduped: Vec<Self> = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let contained = deduped.last().is_some_and(|prev| prev.contains(&block));
+            if !contained {
+                deduped.push(block);
+            }
+        }
+
+        loop {
+            let mut merged = Vec::with_capacity(deduped.len());
+            let mut changed = false;
+            let mut iter = deduped.into_iter().peekable();
+
+            while let Some(block) = iter.next() {
+                match iter.peek() {
+                    Some(next) if block.is_sibling_of(next) => {
+                        iter.next();
+                        merged.push(block.with_value(block.as_int(), block.mask() - 1));
+                        changed = true;
+                    }
+                    _ => merged.push(block),
+                }
+            }
+
+            if !changed {
+                return merged;
+            }
+
+            merged.sort_unstable_by_key(|b| (b.as_int(), b.mask()));
+            deduped = merged;
+        }
+    }
+
+    // Whether 'self' fully covers 'other', i.e. 'other' is the same address
+    // family, at least as specific, and falls inside self's network
+    fn contains(&self, other: &Self) -> bool {
+        if self.address_width() != other.address_width() || self.mask() > other.mask() {
+            return false;
+        }
+        let shift = self.address_width() - self.mask();
+        shr_safe(self.as_int(), shift) == shr_safe(other.as_int(), shift)
+    }
+
+    // Whether 'self' and 'other' are the two halves of a single less-specific
+    // prefix: same mask, networks differing only in the lowest network bit,
+    // with the lower one aligned to the combined, one-bit-shorter prefix
+    fn is_sibling_of(&self, other: &Self) -> bool {
+        if self.address_width() != other.address_width()
+            || self.mask() != other.mask()
+            || self.mask() == 0
+        {
+            return false;
+        }
+
+        let bit = 1_u128 << (self.address_width() - self.mask());
+        let lower = self.as_int().min(other.as_int());
+        let higher = self.as_int().max(other.as_int());
+
+        higher == lower + bit
+            && lower & low_bits_mask(self.address_width() - self.mask() + 1) == 0
+    }
+
+    // Whether 'addr' falls inside this block's network, i.e. shares the same
+    // network id under this block's mask. Only applies to IPv4 blocks
+    pub fn contains_address(&self, addr: [u8; 4]) -> bool {
+        match self {
+            Self::V4 { mask, .. } => self.contains(&Self::new(addr, *mask)),
+            Self::V6 { .. } => false,
+        }
+    }
+
+    // Whether 'other' is fully covered by this block, i.e. 'other' is at
+    // least as specific and its network falls inside this one
+    pub fn contains_block(&self, other: &Self) -> bool {
+        self.contains(other)
+    }
+
+    // Whether this block and 'other' share any address
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.contains(other) || other.contains(self)
+    }
+
+    // Computes the minimal, aligned CIDR cover of the address space inside
+    // 'self' that is not covered by any block in 'excluded': the part of the
+    // parent left over once the excluded subnets have been carved out
+    pub fn complement(&self, excluded: &[Self]) -> Vec<Self> {
+        if !excluded.iter().any(|e| self.overlaps(e)) {
+            return vec![*self];
+        }
+        if excluded.iter().any(|e| e.contains_block(self)) {
+            return Vec::new();
+        }
+        if self.mask() >= self.address_width() {
+            // A single address can't be split any further; since it wasn't
+            // fully excluded above, it must be partially excluded, which is
+            // impossible for a /32 or /128, so there is nothing left to carve
+            return Vec::new();
+        }
+
+        let child_mask = self.mask() + 1;
+        let half_size = 1_u128 << (self.address_width() - child_mask);
+        let lower = self.with_value(self.as_int(), child_mask);
+        let upper = self.with_value(self.as_int() + half_size, child_mask);
+
+        let mut result = lower.complement(excluded);
+        result.extend(upper.complement(excluded));
+        result
+    }
+
+    // Lazily yields every usable host address in this IPv4 block, so that
+    // e.g. a /16 doesn't need to allocate. Yields nothing for IPv6 blocks or
+    // for blocks with no usable-host range (/31, /32)
+    pub fn hosts(&self) -> impl Iterator<Item = [u8; 4]> + '_ {
+        let range = match (self.first_host(), self.last_host()) {
+            (Some(first), Some(last)) if !self.is_v6() => {
+                Some((first.address_as_u32(), last.address_as_u32()))
+            }
+            _ => None,
+        };
+        let (start, end) = range.unwrap_or((1, 0));
+
+        (start..=end).map(|as_u32| match Self::from_u32_address(as_u32, 32) {
+            Self::V4 { address, .. } => address,
+            Self::V6 { .. } => unreachable!("from_u32_address always builds a V4 block"),
+        })
+    }
+
+    // Converts the address to a u32. Panics if called on a v6 block
     pub fn address_as_u32(&self) -> u32 {
-        // 'self.address' is an array of four u8, so it is cheap to copy them
-        self.address
-            .into_iter()
-            .fold(0_u32, |acc, octet| (acc << 8) + octet as u32)
+        match self {
+            // 'address' is an array of four u8, so it is cheap to copy them
+            Self::V4 { address, .. } => address
+                .iter()
+                .fold(0_u32, |acc, &octet| (acc << 8) + octet as u32),
+            Self::V6 { .. } => panic!("address_as_u32 called on an IPv6 block"),
+        }
+    }
+
+    // Converts the address to its numeric representation, widened to a u128 so
+    // the same arithmetic works for both address families
+    pub fn as_int(&self) -> u128 {
+        match self {
+            Self::V4 { address, .. } => address
+                .iter()
+                .fold(0_u128, |acc, &octet| (acc << 8) + octet as u128),
+            Self::V6 { address, .. } => address
+                .iter()
+                .fold(0_u128, |acc, &hextet| (acc << 16) + hextet as u128),
+        }
+    }
+
+    // Builds a block of the same address family as 'self' from a numeric
+    // address and a mask
+    fn with_value(&self, value: u128, mask: u8) -> Self {
+        match self {
+            Self::V4 { .. } => Self::from_u32_address(value as u32, mask),
+            Self::V6 { .. } => Self::from_u128_address(value, mask),
+        }
     }
 
     fn new_mask_for(&self, num_networks: usize) -> Option<u8> {
@@ -169,10 +570,10 @@ impl IpAddressBlock {
             return None;
         }
 
-        let bits_needed = minimum_bits_needed(num_networks);
-        let mask = self.mask + bits_needed;
+        let bits_needed = minimum_bits_needed(num_networks as u128);
+        let mask = self.mask() + bits_needed;
 
-        if mask <= 32 {
+        if mask <= self.address_width() {
             Some(mask)
         } else {
             None
@@ -180,6 +581,72 @@ impl IpAddressBlock {
     }
 }
 
+impl IpAddressBlock {
+    // The address alone, without the trailing '/mask', e.g. "192.168.1.1" or
+    // "2001:db8::1". Useful for callers that already track the prefix length
+    // separately and don't want it repeated in the address text
+    pub fn address_string(&self) -> String {
+        match self {
+            Self::V4 { address, .. } => format!("{}.{}.{}.{}", address[0], address[1], address[2], address[3]),
+            Self::V6 { address, .. } => format_v6_compressed(address),
+        }
+    }
+}
+
+impl Display for IpAddressBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address_string(), self.mask())
+    }
+}
+
+// Renders the eight hextets using the canonical "::" zero-compression form
+// described in RFC 5952: the longest run of two or more all-zero hextets is
+// collapsed, with ties broken in favor of the leftmost run.
+fn format_v6_compressed(address: &[u16; 8]) -> String {
+    let mut best_run: Option<(usize, usize)> = None; // (start, len)
+    let mut cur_start = None;
+
+    for (idx, &hextet) in address.iter().enumerate() {
+        if hextet == 0 {
+            if cur_start.is_none() {
+                cur_start = Some(idx);
+            }
+        } else if let Some(start) = cur_start.take() {
+            let len = idx - start;
+            if len >= 2 && best_run.is_none_or(|(_, best_len)| len > best_len) {
+                best_run = Some((start, len));
+            }
+        }
+    }
+    if let Some(start) = cur_start {
+        let len = 8 - start;
+        if len >= 2 && best_run.is_none_or(|(_, best_len)| len > best_len) {
+            best_run = Some((start, len));
+        }
+    }
+
+    match best_run {
+        Some((start, len)) => {
+            let head = address[..start]
+                .iter()
+                .map(|h| format!("{h:x}"))
+                .collect::<Vec<_>>()
+                .join(":");
+            let tail = address[start + len..]
+                .iter()
+                .map(|h| format!("{h:x}"))
+                .collect::<Vec<_>>()
+                .join(":");
+            format!("{head}::{tail}")
+        }
+        None => address
+            .iter()
+            .map(|h| format!("{h:x}"))
+            .collect::<Vec<_>>()
+            .join(":"),
+    }
+}
+
 fn parse_octet(octet: &str) -> Result<u8, <IpAddressBlock as FromStr>::Err> {
     // If the symbol '+' is present in the octet, then it is an error
     // although the method parse::<u8>() from str returns the Ok variant if that
@@ -233,18 +700,99 @@ fn extract_address_and_mask(s: &str) -> Result<([u8; 4], u8), <IpAddressBlock as
     Ok((address, mask))
 }
 
+// Parses the hextets of an IPv6 address, honoring a single "::"
+// zero-compression run, and the '/mask' suffix
+fn extract_address_and_mask_v6(s: &str) -> Result<([u16; 8], u8), <IpAddressBlock as FromStr>::Err> {
+    let mut parts = s.splitn(2, '/');
+    let addr_str = parts.next().expect("split iterator must have at least one element");
+    let mask_str = parts.next().ok_or(IpAddressParseError {
+        kind: IpAddressErrorKind::MissingMask,
+    })?;
+
+    let mask = mask_str.parse::<u8>().map_err(|_| IpAddressParseError {
+        kind: IpAddressErrorKind::MaskOutOfRange(mask_str.to_string()),
+    })?;
+
+    let compression_count = addr_str.matches("::").count();
+    if compression_count > 1 {
+        return Err(IpAddressParseError {
+            kind: IpAddressErrorKind::IncorrectFormat,
+        });
+    }
+
+    let parse_hextet = |hextet: &str| {
+        u16::from_str_radix(hextet, 16).map_err(|_| IpAddressParseError {
+            kind: IpAddressErrorKind::OctetOutOfRange(hextet.to_string()),
+        })
+    };
+
+    let mut hextets = [0_u16; 8];
+
+    if let Some((head, tail)) = addr_str.split_once("::") {
+        let head_parts: Vec<&str> = if head.is_empty() {
+            Vec::new()
+        } else {
+            head.split(':').collect()
+        };
+        let tail_parts: Vec<&str> = if tail.is_empty() {
+            Vec::new()
+        } else {
+            tail.split(':').collect()
+        };
+
+        if head_parts.len() + tail_parts.len() > 7 {
+            return Err(IpAddressParseError {
+                kind: IpAddressErrorKind::IncorrectFormat,
+            });
+        }
+
+        for (idx, part) in head_parts.iter().enumerate() {
+            hextets[idx] = parse_hextet(part)?;
+        }
+        let tail_start = 8 - tail_parts.len();
+        for (idx, part) in tail_parts.iter().enumerate() {
+            hextets[tail_start + idx] = parse_hextet(part)?;
+        }
+    } else {
+        let parts: Vec<&str> = addr_str.split(':').collect();
+        if parts.len() != 8 {
+            return Err(IpAddressParseError {
+                kind: IpAddressErrorKind::IncorrectFormat,
+            });
+        }
+        for (idx, part) in parts.iter().enumerate() {
+            hextets[idx] = parse_hextet(part)?;
+        }
+    }
+
+    Ok((hextets, mask))
+}
+
 impl FromStr for IpAddressBlock {
     type Err = IpAddressParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (address, mask) = extract_address_and_mask(s)?;
+        // IPv6 addresses always contain a ':', IPv4 addresses never do
+        if s.contains(':') {
+            let (address, mask) = extract_address_and_mask_v6(s)?;
 
-        if mask <= 32 {
-            Ok(Self::new(address, mask))
+            if mask <= 128 {
+                Ok(Self::new_v6(address, mask))
+            } else {
+                Err(IpAddressParseError {
+                    kind: IpAddressErrorKind::MaskOutOfRange(mask.to_string()),
+                })
+            }
         } else {
-            Err(IpAddressParseError {
-                kind: IpAddressErrorKind::MaskOutOfRange(mask.to_string()),
-            })
+            let (address, mask) = extract_address_and_mask(s)?;
+
+            if mask <= 32 {
+                Ok(Self::new(address, mask))
+            } else {
+                Err(IpAddressParseError {
+                    kind: IpAddressErrorKind::MaskOutOfRange(mask.to_string()),
+                })
+            }
         }
     }
 }
@@ -289,15 +837,76 @@ mod tests {
         assert!("213.-23.1.23/32".parse::<IpAddressBlock>().is_err());
     }
 
+    #[test]
+    fn correctly_parse_v6_address() {
+        assert_eq!(
+            "2001:db8::/32".parse::<IpAddressBlock>().unwrap(),
+            IpAddressBlock::new_v6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 0], 32)
+        );
+
+        assert_eq!(
+            "::1/128".parse::<IpAddressBlock>().unwrap(),
+            IpAddressBlock::new_v6([0, 0, 0, 0, 0, 0, 0, 1], 128)
+        );
+
+        assert_eq!(
+            "fe80::a00:27ff:fe4e:66a1/64".parse::<IpAddressBlock>().unwrap(),
+            IpAddressBlock::new_v6([0xfe80, 0, 0, 0, 0xa00, 0x27ff, 0xfe4e, 0x66a1], 64)
+        );
+
+        assert_eq!(
+            "2001:0db8:0:0:0:0:0:1/128".parse::<IpAddressBlock>().unwrap(),
+            IpAddressBlock::new_v6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1], 128)
+        );
+    }
+
+    #[test]
+    fn incorrectly_v6_ip() {
+        assert!("2001:db8::1::2/64".parse::<IpAddressBlock>().is_err());
+        assert!("2001:db8/64".parse::<IpAddressBlock>().is_err());
+        assert!("2001:db8::".parse::<IpAddressBlock>().is_err());
+        assert!("2001:zzzz::/64".parse::<IpAddressBlock>().is_err());
+        assert!("2001:db8::/129".parse::<IpAddressBlock>().is_err());
+    }
+
+    #[test]
+    fn displays_v6_in_compressed_form() {
+        assert_eq!(
+            IpAddressBlock::new_v6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 0], 32).to_string(),
+            "2001:db8::/32"
+        );
+        assert_eq!(
+            IpAddressBlock::new_v6([0, 0, 0, 0, 0, 0, 0, 1], 128).to_string(),
+            "::1/128"
+        );
+        assert_eq!(
+            IpAddressBlock::new_v6([0xfe80, 0, 0, 0, 0xa00, 0x27ff, 0xfe4e, 0x66a1], 64).to_string(),
+            "fe80::a00:27ff:fe4e:66a1/64"
+        );
+    }
+
+    #[test]
+    fn address_string_omits_the_mask() {
+        assert_eq!(
+            IpAddressBlock::new([192, 168, 1, 1], 24).address_string(),
+            "192.168.1.1"
+        );
+        assert_eq!(
+            IpAddressBlock::new_v6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1], 32).address_string(),
+            "2001:db8::1"
+        );
+    }
+
     #[test]
     fn correctly_creates_address_from_u32() {
         let addr = "201.70.64.0/24".parse::<IpAddressBlock>().unwrap();
-        let as_u32 = addr
-            .address
-            .into_iter()
-            .fold(0_u32, |acc, octet| (acc << 8) + octet as u32);
+        assert_eq!(addr, IpAddressBlock::from_u32_address(addr.as_int() as u32, 24));
+    }
 
-        assert_eq!(addr, IpAddressBlock::from_u32_address(as_u32, 24));
+    #[test]
+    fn correctly_creates_address_from_u128() {
+        let addr = "2001:db8::/32".parse::<IpAddressBlock>().unwrap();
+        assert_eq!(addr, IpAddressBlock::from_u128_address(addr.as_int(), 32));
     }
 
     #[test]
@@ -335,14 +944,252 @@ mod tests {
         );
     }
 
+    #[test]
+    fn subnets_flsm_correctly_for_v6() {
+        let addr = "2001:db8::/32".parse::<IpAddressBlock>().unwrap();
+        let expected = vec![
+            "2001:db8::/34".parse::<IpAddressBlock>().unwrap(),
+            "2001:db8:4000::/34".parse::<IpAddressBlock>().unwrap(),
+            "2001:db8:8000::/34".parse::<IpAddressBlock>().unwrap(),
+            "2001:db8:c000::/34".parse::<IpAddressBlock>().unwrap(),
+        ];
+        assert_eq!(addr.subnet_flsm(4).unwrap(), expected);
+    }
+
+    #[test]
+    fn subnets_flsm_does_not_panic_on_v6_slash_zero() {
+        let addr = "::/0".parse::<IpAddressBlock>().unwrap();
+        assert_eq!(addr.subnet_flsm(1).unwrap(), vec![addr]);
+    }
+
+    #[test]
+    fn reports_network_and_broadcast_address() {
+        let addr = "192.168.1.100/27".parse::<IpAddressBlock>().unwrap();
+        assert_eq!(
+            addr.network_address(),
+            "192.168.1.96/27".parse::<IpAddressBlock>().unwrap()
+        );
+        assert_eq!(
+            addr.broadcast_address(),
+            "192.168.1.127/27".parse::<IpAddressBlock>().unwrap()
+        );
+        assert_eq!(
+            addr.first_host().unwrap(),
+            "192.168.1.97/27".parse::<IpAddressBlock>().unwrap()
+        );
+        assert_eq!(
+            addr.last_host().unwrap(),
+            "192.168.1.126/27".parse::<IpAddressBlock>().unwrap()
+        );
+        assert_eq!(addr.host_count(), 30);
+    }
+
+    #[test]
+    fn does_not_panic_on_v6_slash_zero_queries() {
+        let addr = "2001:db8::/0".parse::<IpAddressBlock>().unwrap();
+        assert_eq!(addr.network_address(), "::/0".parse::<IpAddressBlock>().unwrap());
+        assert_eq!(
+            addr.broadcast_address(),
+            "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/0".parse::<IpAddressBlock>().unwrap()
+        );
+        assert_eq!(addr.host_count(), u128::MAX - 1);
+        assert_eq!(addr.available_hosts(), u128::MAX - 1);
+    }
+
+    #[test]
+    fn handles_point_to_point_and_host_masks() {
+        let addr = "10.0.0.0/31".parse::<IpAddressBlock>().unwrap();
+        assert!(addr.first_host().is_none());
+        assert!(addr.last_host().is_none());
+        assert_eq!(addr.host_count(), 0);
+
+        let addr = "10.0.0.5/32".parse::<IpAddressBlock>().unwrap();
+        assert!(addr.first_host().is_none());
+        assert!(addr.last_host().is_none());
+        assert_eq!(addr.host_count(), 0);
+        assert_eq!(addr.network_address(), addr.broadcast_address());
+    }
+
+    #[test]
+    fn displays_subnet_mask_and_wildcard() {
+        let addr = "10.1.2.3/27".parse::<IpAddressBlock>().unwrap();
+        let mask = addr.subnet_mask().unwrap();
+        assert_eq!(mask.to_string(), "255.255.255.224");
+        assert_eq!(mask.wildcard().to_string(), "0.0.0.31");
+
+        assert!("2001:db8::/32"
+            .parse::<IpAddressBlock>()
+            .unwrap()
+            .subnet_mask()
+            .is_none());
+    }
+
+    #[test]
+    fn decomposes_range_into_minimal_prefixes() {
+        let start = IpAddressBlock::new([192, 168, 1, 5], 32).address_as_u32();
+        let end = IpAddressBlock::new([192, 168, 1, 8], 32).address_as_u32();
+
+        let expected = vec![
+            "192.168.1.5/32".parse::<IpAddressBlock>().unwrap(),
+            "192.168.1.6/31".parse::<IpAddressBlock>().unwrap(),
+            "192.168.1.8/32".parse::<IpAddressBlock>().unwrap(),
+        ];
+        assert_eq!(IpAddressBlock::range_to_prefixes(start, end), expected);
+    }
+
+    #[test]
+    fn decomposes_whole_address_space() {
+        let blocks = IpAddressBlock::range_to_prefixes(0, u32::MAX);
+        assert_eq!(blocks, vec!["0.0.0.0/0".parse::<IpAddressBlock>().unwrap()]);
+    }
+
+    #[test]
+    fn range_to_prefixes_empty_when_start_after_end() {
+        assert!(IpAddressBlock::range_to_prefixes(10, 5).is_empty());
+    }
+
+    #[test]
+    fn aggregates_adjacent_siblings() {
+        let blocks = vec![
+            "192.168.0.0/25".parse::<IpAddressBlock>().unwrap(),
+            "192.168.0.128/25".parse::<IpAddressBlock>().unwrap(),
+        ];
+        assert_eq!(
+            IpAddressBlock::aggregate(blocks),
+            vec!["192.168.0.0/24".parse::<IpAddressBlock>().unwrap()]
+        );
+    }
+
+    #[test]
+    fn aggregates_chains_of_siblings_to_a_fixed_point() {
+        let blocks = vec![
+            "10.0.0.0/26".parse::<IpAddressBlock>().unwrap(),
+            "10.0.0.64/26".parse::<IpAddressBlock>().unwrap(),
+            "10.0.0.128/26".parse::<IpAddressBlock>().unwrap(),
+            "10.0.0.192/26".parse::<IpAddressBlock>().unwrap(),
+        ];
+        assert_eq!(
+            IpAddressBlock::aggregate(blocks),
+            vec!["10.0.0.0/24".parse::<IpAddressBlock>().unwrap()]
+        );
+    }
+
+    #[test]
+    fn aggregates_siblings_entered_with_unaligned_host_addresses() {
+        let blocks = vec![
+            "10.0.0.5/26".parse::<IpAddressBlock>().unwrap(),
+            "10.0.0.70/26".parse::<IpAddressBlock>().unwrap(),
+        ];
+        assert_eq!(
+            IpAddressBlock::aggregate(blocks),
+            vec!["10.0.0.0/25".parse::<IpAddressBlock>().unwrap()]
+        );
+    }
+
+    #[test]
+    fn aggregate_drops_contained_blocks_and_keeps_unrelated_ones() {
+        let blocks = vec![
+            "10.0.0.0/24".parse::<IpAddressBlock>().unwrap(),
+            "10.0.0.64/26".parse::<IpAddressBlock>().unwrap(),
+            "172.16.0.0/25".parse::<IpAddressBlock>().unwrap(),
+        ];
+        assert_eq!(
+            IpAddressBlock::aggregate(blocks),
+            vec![
+                "10.0.0.0/24".parse::<IpAddressBlock>().unwrap(),
+                "172.16.0.0/25".parse::<IpAddressBlock>().unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_containment_and_overlap() {
+        let parent = "10.0.0.0/24".parse::<IpAddressBlock>().unwrap();
+        let child = "10.0.0.64/26".parse::<IpAddressBlock>().unwrap();
+        let unrelated = "172.16.0.0/24".parse::<IpAddressBlock>().unwrap();
+
+        assert!(parent.contains_address([10, 0, 0, 200]));
+        assert!(!parent.contains_address([10, 0, 1, 1]));
+
+        assert!(parent.contains_block(&child));
+        assert!(!child.contains_block(&parent));
+
+        assert!(parent.overlaps(&child));
+        assert!(child.overlaps(&parent));
+        assert!(!parent.overlaps(&unrelated));
+    }
+
+    #[test]
+    fn complement_carves_out_excluded_subnets() {
+        let parent = "192.168.0.0/24".parse::<IpAddressBlock>().unwrap();
+        let excluded = vec![
+            "192.168.0.0/26".parse::<IpAddressBlock>().unwrap(),
+            "192.168.0.192/26".parse::<IpAddressBlock>().unwrap(),
+        ];
+
+        let expected = vec![
+            "192.168.0.64/26".parse::<IpAddressBlock>().unwrap(),
+            "192.168.0.128/26".parse::<IpAddressBlock>().unwrap(),
+        ];
+        assert_eq!(parent.complement(&excluded), expected);
+    }
+
+    #[test]
+    fn complement_is_whole_parent_when_nothing_excluded() {
+        let parent = "192.168.0.0/24".parse::<IpAddressBlock>().unwrap();
+        assert_eq!(parent.complement(&[]), vec![parent]);
+    }
+
+    #[test]
+    fn complement_is_empty_when_fully_excluded() {
+        let parent = "192.168.0.0/25".parse::<IpAddressBlock>().unwrap();
+        let excluded = vec!["192.168.0.0/24".parse::<IpAddressBlock>().unwrap()];
+        assert!(parent.complement(&excluded).is_empty());
+    }
+
+    #[test]
+    fn iterates_usable_hosts_lazily() {
+        let block = "192.168.0.0/29".parse::<IpAddressBlock>().unwrap();
+        let hosts: Vec<_> = block.hosts().collect();
+        assert_eq!(
+            hosts,
+            vec![
+                [192, 168, 0, 1],
+                [192, 168, 0, 2],
+                [192, 168, 0, 3],
+                [192, 168, 0, 4],
+                [192, 168, 0, 5],
+                [192, 168, 0, 6],
+            ]
+        );
+
+        let point_to_point = "10.0.0.0/31".parse::<IpAddressBlock>().unwrap();
+        assert_eq!(point_to_point.hosts().count(), 0);
+
+        let v6 = "2001:db8::/64".parse::<IpAddressBlock>().unwrap();
+        assert_eq!(v6.hosts().count(), 0);
+    }
+
     #[test]
     fn required_mask_for_hosts() {
-        assert_eq!(NetworkHosts::new(30000).required_mask(), 17);
-        assert_eq!(NetworkHosts::new(16383).required_mask(), 17);
-        assert_eq!(NetworkHosts::new(16381).required_mask(), 18);
-        assert_eq!(NetworkHosts::new(8000).required_mask(), 19);
-        assert_eq!(NetworkHosts::new(2).required_mask(), 30);
-        assert_eq!(NetworkHosts::new(1).required_mask(), 30);
+        assert_eq!(NetworkHosts::new(30000).required_mask(32), Some(17));
+        assert_eq!(NetworkHosts::new(16383).required_mask(32), Some(17));
+        assert_eq!(NetworkHosts::new(16381).required_mask(32), Some(18));
+        assert_eq!(NetworkHosts::new(8000).required_mask(32), Some(19));
+        assert_eq!(NetworkHosts::new(2).required_mask(32), Some(30));
+        assert_eq!(NetworkHosts::new(1).required_mask(32), Some(30));
+    }
+
+    #[test]
+    fn required_mask_is_width_aware_and_none_when_it_does_not_fit() {
+        assert_eq!(NetworkHosts::new(1_000_000_000_000).required_mask(128), Some(88));
+        assert_eq!(NetworkHosts::new(1_000_000_000_000).required_mask(32), None);
+    }
+
+    #[test]
+    fn required_mask_does_not_overflow_for_near_u128_max_host_counts() {
+        assert_eq!(NetworkHosts::new(u128::MAX - 1).required_mask(128), Some(0));
+        assert_eq!(NetworkHosts::new(u128::MAX).required_mask(128), Some(0));
     }
 
     #[test]
@@ -414,4 +1261,28 @@ mod tests {
         ];
         assert_eq!(addr.subnet_vlsm(nets).unwrap(), expected);
     }
+
+    #[test]
+    fn subnets_vlsm_correctly_for_v6() {
+        let addr = "2001:db8::/32".parse::<IpAddressBlock>().unwrap();
+        let nets = vec![NetworkHosts::new(1000), NetworkHosts::new(2000)];
+
+        let expected = vec![
+            (
+                NetworkHosts::new(2000),
+                "2001:db8::/117".parse::<IpAddressBlock>().unwrap(),
+            ),
+            (
+                NetworkHosts::new(1000),
+                "2001:db8::800/118".parse::<IpAddressBlock>().unwrap(),
+            ),
+        ];
+        assert_eq!(addr.subnet_vlsm(nets).unwrap(), expected);
+    }
+
+    #[test]
+    fn parses_network_hosts_from_str() {
+        assert_eq!("1000".parse::<NetworkHosts>().unwrap(), NetworkHosts::new(1000));
+        assert!("not-a-number".parse::<NetworkHosts>().is_err());
+    }
 }
@@ -1,104 +1,335 @@
-use std::{env, error::Error};
+use std::{
+    env,
+    error::Error,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    str::FromStr,
+};
+
+use flate2::read::GzDecoder;
+use serde::Serialize;
 
 use subnetcalc::{IpAddressBlock, NetworkHosts};
 
-const HELP: &str = "usage: subnetcalc IPADDR_BLOCK OPTION ARGS
+const HELP: &str = "usage: subnetcalc [--output FORMAT] IPADDR_BLOCK OPTION ARGS
+       subnetcalc [--output FORMAT] --aggregate PREFIX...
 
 * IPADDR_BLOCK: Address block which is going to be divided
 * OPTION: Strategy to follow to divide the address block
-    --vlsm | -v: Uses the Variable Length Subnet Mask (VLSM) strategy. In this case, ARGS is a 
+    --vlsm | -v: Uses the Variable Length Subnet Mask (VLSM) strategy. In this case, ARGS is a
     space separated set of numbers which represent the number of host each network is going to have
-    --flsm | -f: Uses the Fixed Length Subnet Mask (FLSM) strategy. In this case ARGS is the number 
-    of subnets you want";
+    --flsm | -f: Uses the Fixed Length Subnet Mask (FLSM) strategy. In this case ARGS is the number
+    of subnets you want
+    --invert | -i: Given IPADDR_BLOCK as the parent network, ARGS is a space separated set of
+    already-allocated child subnets, and the tool prints the unused address space that remains
+* --aggregate | -a PREFIX...: Merges the given list of CIDR prefixes into the smallest equivalent set
+* --output | -o FORMAT: How to print the result: 'text' (default), 'json' or 'csv'
+
+Instead of a space separated list, --vlsm and --aggregate also accept a single ARGS of the form
+@path (or '-' for stdin) to read one requirement/prefix per line. A '.gz' suffix on the path is
+transparently decompressed. Blank lines and lines starting with '#' are skipped.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Box<dyn Error + 'static>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!("'{s}' is not a valid output format").into()),
+        }
+    }
+}
 
 #[derive(Debug)]
 enum CliOption {
-    Run(Config),
+    Run(RunOptions, OutputFormat),
     Help,
 }
 
 impl CliOption {
-    fn parse(mut args: env::Args) -> Result<Self, Box<dyn Error + 'static>> {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, Box<dyn Error + 'static>> {
         // FIXME: change error type
 
         // The first element is the executable name, so we ignore it
-        args.next();
+        let mut args: Vec<String> = args.collect();
+        args.remove(0);
+
+        let output_format = extract_output_format(&mut args)?;
+        let mut args = args.into_iter();
 
         let ipaddr_block = match args.next() {
             Some(text) if text == "--help" || text == "-h" => return Ok(CliOption::Help),
+            Some(text) if text == "--aggregate" || text == "-a" => {
+                let prefixes = collect_values(args)?;
+                return Ok(CliOption::Run(RunOptions::Aggregate(prefixes), output_format));
+            }
             Some(ipaddr_block) => ipaddr_block.parse()?,
             None => Err("missing first option")?,
         };
 
         let option = match args.next() {
             Some(opt) if opt == "--vlsm" || opt == "-v" => {
-                let results = args
-                    .map(|arg| arg.parse::<NetworkHosts>())
-                    .collect::<Result<Vec<_>, _>>()?;
-                RunOptions::VLSM(results)
+                let subnets = collect_values(args)?;
+                RunOptions::Vlsm { ipaddr_block, subnets }
             }
             Some(opt) if opt == "--flsm" || opt == "-f" => {
-                let num = args
+                let num_networks = args
                     .next()
                     .ok_or("missing option")?
                     .parse::<usize>()
                     .map_err(|_| "invalid option")?;
-                RunOptions::FLSM(num)
+                RunOptions::Flsm { ipaddr_block, num_networks }
+            }
+            Some(opt) if opt == "--invert" || opt == "-i" => {
+                let excluded = collect_values(args)?;
+                RunOptions::Invert { parent: ipaddr_block, excluded }
             }
             Some(_) => Err("invalid option")?,
             None => Err("missing option")?,
         };
 
-        Ok(CliOption::Run(Config {
-            ipaddr_block,
-            option,
-        }))
+        Ok(CliOption::Run(option, output_format))
+    }
+}
+
+// Pulls the '--output'/'-o' flag and its value out of 'args', wherever it
+// appears, leaving the rest of the arguments untouched
+fn extract_output_format(args: &mut Vec<String>) -> Result<OutputFormat, Box<dyn Error + 'static>> {
+    let Some(idx) = args.iter().position(|arg| arg == "--output" || arg == "-o") else {
+        return Ok(OutputFormat::Text);
+    };
+
+    if idx + 1 >= args.len() {
+        return Err("missing value for --output".into());
     }
+
+    let format = args[idx + 1].parse()?;
+    args.drain(idx..=idx + 1);
+    Ok(format)
+}
+
+// Whether 'arg' names an external source of values rather than being a value
+// itself: either '@path' or '-' for stdin
+fn is_source_spec(arg: &str) -> bool {
+    arg == "-" || arg.starts_with('@')
+}
+
+// Opens the file or stdin named by a '@path'/'-' source spec, transparently
+// decompressing it if the path ends in '.gz'
+fn open_source(source: &str) -> Result<Box<dyn BufRead>, Box<dyn Error + 'static>> {
+    if source == "-" {
+        return Ok(Box::new(BufReader::new(io::stdin())));
+    }
+
+    let path = source.strip_prefix('@').unwrap_or(source);
+    let file = File::open(path)?;
+
+    if path.ends_with(".gz") {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+// Reads one requirement/prefix per non-blank, non-comment line from a
+// '@path'/'-' source spec
+fn read_lines(source: &str) -> Result<Vec<String>, Box<dyn Error + 'static>> {
+    let mut lines = Vec::new();
+
+    for line in open_source(source)?.lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            lines.push(line.to_string());
+        }
+    }
+
+    Ok(lines)
+}
+
+// Parses the remaining CLI arguments into a list of 'T'. If exactly one
+// argument is given and it is a '@path'/'-' source spec, the values are read
+// from it (one per line) instead, which lets '--vlsm'/'--aggregate' take
+// batch input without shell-quoting hundreds of values
+fn collect_values<T>(args: impl Iterator<Item = String>) -> Result<Vec<T>, Box<dyn Error + 'static>>
+where
+    T: FromStr,
+    T::Err: Error + 'static,
+{
+    let raw_args: Vec<String> = args.collect();
+
+    let values = match raw_args.as_slice() {
+        [source] if is_source_spec(source) => read_lines(source)?,
+        _ => raw_args,
+    };
+
+    values
+        .iter()
+        .map(|value| value.parse::<T>().map_err(|e| Box::new(e) as Box<dyn Error + 'static>))
+        .collect()
 }
 
 #[derive(Debug)]
 enum RunOptions {
-    FLSM(usize),
-    VLSM(Vec<NetworkHosts>),
+    Flsm {
+        ipaddr_block: IpAddressBlock,
+        num_networks: usize,
+    },
+    Vlsm {
+        ipaddr_block: IpAddressBlock,
+        subnets: Vec<NetworkHosts>,
+    },
+    Aggregate(Vec<IpAddressBlock>),
+    Invert {
+        parent: IpAddressBlock,
+        excluded: Vec<IpAddressBlock>,
+    },
 }
 
-#[derive(Debug)]
-struct Config {
-    ipaddr_block: IpAddressBlock,
-    option: RunOptions,
+// A single subnet in a machine-readable report: the network itself, plus the
+// derived fields (mask, broadcast, usable host range) a subnet calculator is
+// expected to show, and, for VLSM, how many hosts were requested
+#[derive(Debug, Serialize)]
+struct SubnetReport {
+    network: String,
+    prefix_len: u8,
+    mask: Option<String>,
+    broadcast: String,
+    first_host: Option<String>,
+    last_host: Option<String>,
+    host_count: u128,
+    requested_hosts: Option<u128>,
 }
 
-fn show_subnets(config: Config) -> Result<(), Box<dyn Error + 'static>> {
-    match config.option {
-        RunOptions::FLSM(target_subnets) => match config.ipaddr_block.subnet_flsm(target_subnets) {
-            Some(result) => {
-                for (idx, subnet) in result.iter().enumerate() {
-                    println!("{}) {}", idx + 1, subnet);
-                }
-            }
-            None => println!(
-                "It is not possible to divide {} in {} subnetworks using FLSM",
-                config.ipaddr_block, target_subnets
+impl SubnetReport {
+    fn new(block: &IpAddressBlock, requested_hosts: Option<u128>) -> Self {
+        Self {
+            network: block.to_string(),
+            prefix_len: block.mask(),
+            mask: block.subnet_mask().map(|mask| mask.to_string()),
+            broadcast: block.broadcast_address().address_string(),
+            first_host: block.first_host().map(|host| host.address_string()),
+            last_host: block.last_host().map(|host| host.address_string()),
+            host_count: block.host_count(),
+            requested_hosts,
+        }
+    }
+}
+
+// The full result of a run: either the list of subnets produced, or the
+// reason division wasn't possible. Serialized as a structured error object
+// instead of the human-readable line printed in text mode
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum RunResult {
+    Subnets(Vec<SubnetReport>),
+    Error { error: String },
+}
+
+fn compute_result(option: RunOptions) -> RunResult {
+    match option {
+        RunOptions::Flsm { ipaddr_block, num_networks } => match ipaddr_block.subnet_flsm(num_networks) {
+            Some(blocks) => RunResult::Subnets(blocks.iter().map(|b| SubnetReport::new(b, None)).collect()),
+            None => RunResult::Error {
+                error: format!(
+                    "It is not possible to divide {} in {} subnetworks using FLSM",
+                    ipaddr_block, num_networks
+                ),
+            },
+        },
+        RunOptions::Vlsm { ipaddr_block, subnets } => match ipaddr_block.subnet_vlsm(subnets) {
+            Some(allocations) => RunResult::Subnets(
+                allocations
+                    .iter()
+                    .map(|(hosts, block)| SubnetReport::new(block, Some(hosts.hosts())))
+                    .collect(),
             ),
+            None => RunResult::Error {
+                error: format!(
+                    "It is not possible to subnet {} using VLSM with those requirements",
+                    ipaddr_block
+                ),
+            },
         },
-        RunOptions::VLSM(nets) => match config.ipaddr_block.subnet_vlsm(nets) {
-            Some(result) => {
-                for (idx, (net_hosts, nets)) in result.iter().enumerate() {
-                    println!("{}) {} - {}", idx + 1, net_hosts.hosts(), nets);
+        RunOptions::Aggregate(prefixes) => RunResult::Subnets(
+            IpAddressBlock::aggregate(prefixes)
+                .iter()
+                .map(|b| SubnetReport::new(b, None))
+                .collect(),
+        ),
+        RunOptions::Invert { parent, excluded } => {
+            let free = parent.complement(&excluded);
+            if free.is_empty() {
+                RunResult::Error {
+                    error: format!("There is no free address space left in {}", parent),
                 }
+            } else {
+                RunResult::Subnets(free.iter().map(|b| SubnetReport::new(b, None)).collect())
             }
-            None => println!(
-                "It is not possible to subnet {} using VLSM with those requirements",
-                config.ipaddr_block
-            ),
-        },
+        }
     }
+}
+
+fn print_text(result: &RunResult) {
+    match result {
+        RunResult::Error { error } => println!("{error}"),
+        RunResult::Subnets(reports) => {
+            for (idx, report) in reports.iter().enumerate() {
+                match report.requested_hosts {
+                    Some(requested) => println!("{}) {} - {}", idx + 1, requested, report.network),
+                    None => println!("{}) {}", idx + 1, report.network),
+                }
+            }
+        }
+    }
+}
+
+fn print_csv(result: &RunResult) {
+    match result {
+        RunResult::Error { error } => println!("error\n\"{}\"", error.replace('"', "\"\"")),
+        RunResult::Subnets(reports) => {
+            println!("network,prefix_len,mask,broadcast,first_host,last_host,host_count,requested_hosts");
+            for report in reports {
+                println!(
+                    "{},{},{},{},{},{},{},{}",
+                    report.network,
+                    report.prefix_len,
+                    report.mask.as_deref().unwrap_or(""),
+                    report.broadcast,
+                    report.first_host.as_deref().unwrap_or(""),
+                    report.last_host.as_deref().unwrap_or(""),
+                    report.host_count,
+                    report.requested_hosts.map(|h| h.to_string()).unwrap_or_default(),
+                );
+            }
+        }
+    }
+}
+
+fn show_subnets(option: RunOptions, format: OutputFormat) -> Result<(), Box<dyn Error + 'static>> {
+    let result = compute_result(option);
+
+    match format {
+        OutputFormat::Text => print_text(&result),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        OutputFormat::Csv => print_csv(&result),
+    }
+
     Ok(())
 }
 
 fn run() -> Result<(), Box<dyn Error + 'static>> {
     match CliOption::parse(env::args())? {
-        CliOption::Run(config) => show_subnets(config),
+        CliOption::Run(option, format) => show_subnets(option, format),
         CliOption::Help => {
             println!("{}", HELP);
             Ok(())